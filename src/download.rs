@@ -0,0 +1,241 @@
+use std::error::Error;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use futures_util::StreamExt;
+use indicatif::{ProgressBar, ProgressStyle};
+use reqwest::{header, Client, StatusCode, Url};
+use sha2::{Digest, Sha256};
+
+/// A finished, verified download leaves this marker next to `cache` so a
+/// later run can tell "fully downloaded" apart from "partial file present,
+/// resume it" without needing a digest to compare against.
+fn marker_path(cache: &Path) -> PathBuf {
+    let mut name = cache.as_os_str().to_os_string();
+    name.push(".complete");
+    PathBuf::from(name)
+}
+
+/// Fetch `url` into `cache`, streaming the body instead of buffering it in
+/// memory, resuming a partial download if one is already on disk, and
+/// verifying the finished file against `expected_digest` (a hex-encoded
+/// MD5 or SHA-256 digest, picked by its length). A mismatching digest
+/// triggers one full re-download before giving up.
+pub async fn fetch_dataset(
+    url: &Url,
+    cache: &Path,
+    expected_digest: Option<&str>,
+) -> Result<PathBuf, Box<dyn Error>> {
+    let marker = marker_path(cache);
+
+    // Only a file carrying the completion marker (and, if we have one, a
+    // matching digest) is considered done. Anything else - including a
+    // cache file left over from an interrupted run - falls through to
+    // `download_with_resume`, which resumes it via a `Range` request.
+    if marker.exists() && digest_matches(cache, expected_digest)? {
+        return Ok(cache.to_path_buf());
+    }
+
+    download_with_resume(url, cache).await?;
+
+    if !digest_matches(cache, expected_digest)? {
+        // Corrupt or truncated download: wipe the cache and retry once from scratch.
+        fs::remove_file(cache)?;
+        let _ = fs::remove_file(&marker);
+        download_with_resume(url, cache).await?;
+
+        if !digest_matches(cache, expected_digest)? {
+            return Err("downloaded file does not match the expected digest".into());
+        }
+    }
+
+    File::create(&marker)?;
+    Ok(cache.to_path_buf())
+}
+
+fn digest_matches(path: &Path, expected: Option<&str>) -> Result<bool, Box<dyn Error>> {
+    let expected = match expected {
+        Some(expected) => expected.to_lowercase(),
+        None => return Ok(true),
+    };
+
+    let actual = match expected.len() {
+        32 => md5_digest(path)?,
+        64 => sha256_digest(path)?,
+        _ => return Err("expected_digest must be a 32-char MD5 or 64-char SHA-256 hex string".into()),
+    };
+
+    Ok(actual == expected)
+}
+
+async fn download_with_resume(url: &Url, cache: &Path) -> Result<(), Box<dyn Error>> {
+    let client = Client::new();
+
+    let resume_from = fs::metadata(cache).map(|meta| meta.len()).unwrap_or(0);
+
+    let mut request = client.get(url.clone());
+    if resume_from > 0 {
+        request = request.header(header::RANGE, format!("bytes={}-", resume_from));
+    }
+
+    let response = request.send().await?;
+
+    if response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        // We asked to resume from byte `resume_from`, and the server says
+        // there's nothing at or past that offset - i.e. the file on disk
+        // is already exactly as long as the remote resource. This happens
+        // when a run was killed after the bytes landed but before the
+        // `.complete` marker was written. Nothing left to download.
+        return Ok(());
+    }
+
+    if !response.status().is_success() {
+        return Err(format!("unable to download dataset: {}", response.status()).into());
+    }
+
+    let resuming = response.status() == StatusCode::PARTIAL_CONTENT;
+    let already_downloaded = if resuming { resume_from } else { 0 };
+
+    let total_size = response
+        .content_length()
+        .map(|len| len + already_downloaded)
+        .unwrap_or(already_downloaded);
+
+    let progress = ProgressBar::new(total_size);
+    progress.set_style(
+        ProgressStyle::with_template("{bar:40.cyan/blue} {bytes}/{total_bytes} ({eta})")
+            .unwrap_or_else(|_| ProgressStyle::default_bar()),
+    );
+    progress.set_position(already_downloaded);
+
+    if let Some(parent) = cache.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resuming)
+        .truncate(!resuming)
+        .open(cache)?;
+
+    let mut stream = response.bytes_stream();
+    let mut downloaded = already_downloaded;
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        file.write_all(&chunk)?;
+        downloaded += chunk.len() as u64;
+        progress.set_position(downloaded);
+    }
+
+    progress.finish_with_message("download complete");
+    Ok(())
+}
+
+fn md5_digest(path: &Path) -> Result<String, Box<dyn Error>> {
+    let mut context = md5::Context::new();
+    hash_file(path, |buf| context.consume(buf))?;
+    Ok(format!("{:x}", context.compute()))
+}
+
+fn sha256_digest(path: &Path) -> Result<String, Box<dyn Error>> {
+    let mut hasher = Sha256::new();
+    hash_file(path, |buf| hasher.update(buf))?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn hash_file(path: &Path, mut update: impl FnMut(&[u8])) -> Result<(), Box<dyn Error>> {
+    let mut file = File::open(path)?;
+    let mut buf = [0u8; 8192];
+    loop {
+        let read = file.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        update(&buf[..read]);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn temp_path(name: &str) -> PathBuf {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let n = COUNTER.fetch_add(1, Ordering::SeqCst);
+        std::env::temp_dir().join(format!("crate_download_test_{}_{}_{}", std::process::id(), n, name))
+    }
+
+    #[test]
+    fn marker_path_appends_complete() {
+        assert_eq!(
+            marker_path(Path::new("data/set.tgz")),
+            PathBuf::from("data/set.tgz.complete")
+        );
+    }
+
+    #[test]
+    fn md5_digest_matches_a_known_hash() {
+        let path = temp_path("md5.bin");
+        fs::write(&path, b"hello").unwrap();
+
+        assert_eq!(
+            md5_digest(&path).unwrap(),
+            "5d41402abc4b2a76b9719d911017c592"
+        );
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn sha256_digest_matches_a_known_hash() {
+        let path = temp_path("sha256.bin");
+        fs::write(&path, b"hello").unwrap();
+
+        assert_eq!(
+            sha256_digest(&path).unwrap(),
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn digest_matches_is_case_insensitive_and_picks_the_algorithm_by_length() {
+        let path = temp_path("digest_matches.bin");
+        fs::write(&path, b"hello").unwrap();
+
+        assert!(digest_matches(&path, Some("5D41402ABC4B2A76B9719D911017C592")).unwrap());
+        assert!(digest_matches(
+            &path,
+            Some("2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824")
+        )
+        .unwrap());
+        assert!(!digest_matches(&path, Some("00000000000000000000000000000000")).unwrap());
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn digest_matches_treats_no_expected_digest_as_a_match() {
+        let path = temp_path("no_digest.bin");
+        fs::write(&path, b"hello").unwrap();
+
+        assert!(digest_matches(&path, None).unwrap());
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn digest_matches_rejects_a_digest_of_the_wrong_length() {
+        let path = temp_path("bad_length.bin");
+        fs::write(&path, b"hello").unwrap();
+
+        assert!(digest_matches(&path, Some("deadbeef")).is_err());
+
+        fs::remove_file(&path).unwrap();
+    }
+}
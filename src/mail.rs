@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+
+/// Unfold an email's header block: a line starting with a space/tab is a
+/// continuation of the previous header. Parsing stops at the first blank
+/// line, which marks the end of the headers.
+pub(crate) fn unfold_headers(email: &str) -> HashMap<String, String> {
+    let mut headers: HashMap<String, String> = HashMap::new();
+    let mut last_key: Option<String> = None;
+
+    for line in email.lines() {
+        // A blank line ends the header block.
+        if line.is_empty() {
+            break;
+        }
+
+        if line.starts_with(' ') || line.starts_with('\t') {
+            // Folded continuation of the previous header.
+            if let Some(key) = &last_key {
+                if let Some(value) = headers.get_mut(key) {
+                    value.push(' ');
+                    value.push_str(line.trim());
+                }
+            }
+            continue;
+        }
+
+        if let Some((key, value)) = line.split_once(':') {
+            let key = key.trim().to_lowercase();
+            headers.insert(key.clone(), value.trim().to_string());
+            last_key = Some(key);
+        } else {
+            last_key = None;
+        }
+    }
+
+    headers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unfolds_continuation_lines() {
+        let email = "From: alice@example.com\nSubject: hello\n world\nTo: bob@example.com\n\nbody";
+        let headers = unfold_headers(email);
+
+        assert_eq!(headers.get("subject"), Some(&"hello world".to_string()));
+        assert_eq!(headers.get("from"), Some(&"alice@example.com".to_string()));
+    }
+
+    #[test]
+    fn stops_at_the_blank_line_separating_headers_from_body() {
+        let email = "From: alice@example.com\n\nSubject: not-a-header";
+        let headers = unfold_headers(email);
+
+        assert_eq!(headers.get("from"), Some(&"alice@example.com".to_string()));
+        assert_eq!(headers.get("subject"), None);
+    }
+
+    #[test]
+    fn header_keys_are_case_insensitive() {
+        let email = "FROM: alice@example.com\n\n";
+        let headers = unfold_headers(email);
+
+        assert_eq!(headers.get("from"), Some(&"alice@example.com".to_string()));
+    }
+}
@@ -1,76 +1,43 @@
-use std::io::{self, BufReader, Read};
-use std::path::Path;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
 use std::process;
-use std::collections::{HashMap, HashSet, VecDeque};
 use std::error::Error;
 use rand::seq::SliceRandom;
 use tar::Archive;
 use flate2::read::GzDecoder;
 use reqwest::Url;
-use tokio::runtime::Runtime;
+
+mod download;
+mod graph;
+mod mail;
+mod threads;
+
+use graph::{CsrGraph, Graph};
+use mail::unfold_headers;
 
 // Define a constant
 const DATA_URL: &str = "http://zoo.cs.yale.edu/classes/cs458/lectures/sklearn/ud/ud120-projects-master/enron_mail_20150507.tgz";
 const SAMPLE_SIZE: usize = 10_000;
-
-struct Graph {
-    vertices: HashSet<String>,
-    edges: HashMap<String, HashSet<String>>,
-}
-
-impl Graph {
-    fn new() -> Self {
-        Self {
-            vertices: HashSet::new(),
-            edges: HashMap::new(),
-        }
-    }
-
-    fn add_edge(&mut self, source: &str, target: &str) {
-        self.vertices.insert(source.to_string());
-        self.vertices.insert(target.to_string());
-
-        let source_edges = self.edges.entry(source.to_string()).or_insert(HashSet::new());
-        source_edges.insert(target.to_string());
-
-        let target_edges = self.edges.entry(target.to_string()).or_insert(HashSet::new());
-        target_edges.insert(source.to_string());
-    }
-
-    fn get_neighbors(&self, vertex: &str) -> HashSet<String> {
-        self.edges.get(vertex).cloned().unwrap_or(HashSet::new()) // return  HashSet<String>
-    }
-}
+// Cache location for the downloaded tarball, and its known-good digest once published upstream.
+const CACHE_PATH: &str = "data/enron_mail_20150507.tgz";
+const EXPECTED_DIGEST: Option<&str> = None;
 
 // The first stage: data acquisition and preprocessing
-async fn download_and_extract_data() -> Result<(), Box<dyn Error>> {
-    // 下载数据集
-    let data = download_data().await?;
+async fn download_and_extract_data() -> Result<Vec<String>, Box<dyn Error>> {
+    // 下载数据集 (streamed into a local cache, resuming/verifying as needed)
+    let url = Url::parse(DATA_URL)?;
+    let cache = Path::new(CACHE_PATH);
+    let archive_path = download::fetch_dataset(&url, cache, EXPECTED_DIGEST).await?;
     // 解压缩数据
-    extract_data(data)?;
+    let emails = extract_data(&archive_path)?;
 
-    Ok(())
+    Ok(emails)
 }
 
-async fn download_data() -> Result<Vec<u8>, Box<dyn Error>> {
-// Download the data set
-    let response = reqwest::get(DATA_URL).await?;
-// Check whether the request is successful
-    if !response.status().is_success() {
-        return Err("Unable to download data set".into());
-    }
-
-// Read the response body
-    let mut buf = Vec::new();
-    let bytes = response.bytes().await?;
-    buf.extend_from_slice(&bytes); // Append bytes to the vector using the extend_from_slice method
-
-    Ok(buf)
-}
-
-fn extract_data(data: Vec<u8>) -> Result<(), Box<dyn Error>> {
-// Decompress the data
-    let reader = GzDecoder::new(data.as_slice());
+fn extract_data(archive_path: &Path) -> Result<Vec<String>, Box<dyn Error>> {
+// Decompress the data straight from the cached file instead of from memory
+    let file = std::fs::File::open(archive_path)?;
+    let reader = GzDecoder::new(BufReader::new(file));
     let mut tar = Archive::new(reader); // Declare tar as mutable
 
     // Create a vector to hold the sampled emails
@@ -105,83 +72,223 @@ fn extract_data(data: Vec<u8>) -> Result<(), Box<dyn Error>> {
         println!("{}", email);
     }
 
-    Ok(())
+    Ok(sampled_emails)
 }
 
-// Second stage: Average distance calculation
-fn calculate_average_distance(graph: &Graph) -> f64 {
-    let mut total_distance = 0;
-    let mut total_pairs = 0;
-
-    for vertex in graph.vertices.iter() {
-        let distances = bfs(graph, vertex);
+// Pull the bare `addr@host` out of an RFC-style `Name <addr@host>` form,
+// lowercased, so the same mailbox always maps to the same graph vertex.
+fn normalize_address(raw: &str) -> Option<String> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return None;
+    }
 
-        for (_, distance) in distances.iter() {
-            total_distance += distance;
-            total_pairs += 1;
+    // Use the last `<` (and the `>` after it) so a stray bracket earlier in a
+    // malformed display name doesn't get mistaken for the address's own brackets.
+    let addr = if let Some(start) = raw.rfind('<') {
+        match raw[start..].find('>') {
+            Some(end) => &raw[start + 1..start + end],
+            None => raw,
         }
+    } else {
+        raw
+    };
+
+    let addr = addr.trim();
+    if addr.is_empty() || !addr.contains('@') {
+        return None;
     }
 
-    total_distance as f64 / total_pairs as f64
+    Some(addr.to_lowercase())
 }
 
-fn bfs(graph: &Graph, start_vertex: &str) -> HashMap<String, usize> {
-    let mut distances: HashMap<String, usize> = HashMap::new();
-    let mut visited: HashSet<String> = HashSet::new();
-    let mut queue: VecDeque<(String, usize)> = VecDeque::new(); // Change the queue type to a tuple containing distance
-
-    distances.insert(start_vertex.to_string(), 0);
-    visited.insert(start_vertex.to_string());
-    queue.push_back((start_vertex.to_string(), 0)); // Initialize the starting vertex distance to 0
-
-    while let Some((current_vertex, current_distance)) = queue.pop_front() { // Modify the iteration variable to a tuple
-        for neighbor in graph.get_neighbors(&current_vertex) {
-            if !visited.contains(neighbor.as_str()) {
-                let new_distance = current_distance + 1; // Calculate the new distance
-                distances.insert(neighbor.clone(), new_distance);
-                visited.insert(neighbor.clone());
-                queue.push_back((neighbor.clone(), new_distance)); // Adds the neighbor and new distance to the queue
-            }
-        }
+// Split a comma-separated recipient list (`To:`/`Cc:`) into normalized addresses.
+fn parse_address_list(raw: &str) -> Vec<String> {
+    raw.split(',')
+        .filter_map(normalize_address)
+        .collect()
+}
+
+// Walk one email's headers and record an edge from its sender to every recipient.
+fn add_email_to_graph(graph: &mut Graph, email: &str) {
+    let headers = unfold_headers(email);
+
+    let sender = match headers.get("from").and_then(|v| normalize_address(v)) {
+        Some(sender) => sender,
+        None => return,
+    };
+
+    let mut recipients = Vec::new();
+    if let Some(to) = headers.get("to") {
+        recipients.extend(parse_address_list(to));
+    }
+    if let Some(cc) = headers.get("cc") {
+        recipients.extend(parse_address_list(cc));
     }
 
-    distances
+    for recipient in recipients {
+        graph.add_edge(&sender, &recipient);
+    }
 }
 
-// Stage 3: Degree distribution analysis
-fn degree_distribution_analysis(graph: &Graph) {
-    let mut degrees = HashMap::new();
+// Build the email-interaction graph from the raw message bodies extracted
+// from the archive.
+fn build_graph(emails: &[String]) -> Graph {
+    let mut graph = Graph::new();
 
-    for vertex in graph.vertices.iter() {
-        let degree = graph.get_neighbors(vertex).len();
-        let count = degrees.entry(degree).or_insert(0);
-        *count += 1;
+    for email in emails {
+        add_email_to_graph(&mut graph, email);
     }
 
-    let mut degree_counts: Vec<(usize, usize)> = degrees.into_iter().collect();
-    degree_counts.sort_by_key(|&(degree, _)| degree);
+    graph
+}
+
+// Where the graph for this run comes from: freshly downloaded and parsed
+// Enron emails, or a pre-built edge-list file (see `graph::write_edge_list`).
+enum DataSource {
+    Download,
+    Edges(PathBuf),
+}
+
+struct Args {
+    source: DataSource,
+    // `--save-edges <file>` writes the built graph out so later runs can
+    // skip straight to `--edges <file>` instead of re-downloading.
+    save_edges: Option<PathBuf>,
+}
 
-    println!("Vertex degree distribution:");
-    for (degree, count) in degree_counts.iter() {
-        println!("degree {}: {}", degree, count);
+// Parse `--download` (the default), `--edges <file>`, and `--save-edges <file>` off argv.
+fn parse_args() -> Args {
+    let mut args = std::env::args().skip(1);
+    let mut source = DataSource::Download;
+    let mut save_edges = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--edges" => {
+                if let Some(path) = args.next() {
+                    source = DataSource::Edges(PathBuf::from(path));
+                }
+            }
+            "--download" => source = DataSource::Download,
+            "--save-edges" => {
+                if let Some(path) = args.next() {
+                    save_edges = Some(PathBuf::from(path));
+                }
+            }
+            _ => {}
+        }
     }
+
+    Args { source, save_edges }
 }
 
 #[tokio::main]
 async fn main() {
-// The first stage: data acquisition and preprocessing
-    if let Err(err) = download_and_extract_data().await {
-        eprintln!("Error: {}", err);
-        process::exit(1);
+    let args = parse_args();
+
+// The first stage: data acquisition and preprocessing, or loading a
+// pre-built edge list. Only the download path has raw emails for the
+// thread-reconstruction stage.
+    let (graph, emails) = match args.source {
+        DataSource::Download => {
+            let emails = match download_and_extract_data().await {
+                Ok(emails) => emails,
+                Err(err) => {
+                    eprintln!("Error: {}", err);
+                    process::exit(1);
+                }
+            };
+            let graph = build_graph(&emails);
+            (graph, Some(emails))
+        }
+        DataSource::Edges(path) => {
+            let graph = match Graph::from_edge_list(&path) {
+                Ok(graph) => graph,
+                Err(err) => {
+                    eprintln!("Error: {}", err);
+                    process::exit(1);
+                }
+            };
+            (graph, None)
+        }
+    };
+
+    if let Some(path) = &args.save_edges {
+        if let Err(err) = graph::write_edge_list(&graph, path) {
+            eprintln!("Error: {}", err);
+            process::exit(1);
+        }
     }
 
-// Create a graph representation
-    let graph = Graph::new();
+// Index the graph for analysis
+    let csr = CsrGraph::from_graph(&graph);
 
 // Second stage: Average distance calculation
-    let avg_distance = calculate_average_distance(&graph);
+    let avg_distance = graph::calculate_average_distance(&csr);
     println!("平均距离: {}", avg_distance);
 
 // Stage 3: Degree distribution analysis
-    degree_distribution_analysis(&graph);
+    graph::degree_distribution_analysis(&csr);
+
+// Stage 4: Connected-component analysis, so the average distance above can
+// be read as "average over reachable pairs" rather than assumed global.
+    let component_report = graph::analyze_components(&csr);
+    graph::print_component_report(&component_report);
+    println!("Graph is connected: {}", graph::is_connected(&csr));
+
+    if !csr.is_empty() {
+        let sample = csr.label(0).to_string();
+        let reachable = graph::reachable_from(&csr, &sample);
+        println!("Vertices reachable from {}: {}", sample, reachable.len());
+    }
+
+// Stage 5: Reply thread reconstruction (only available when we parsed emails ourselves)
+    if let Some(emails) = emails {
+        let thread_index = threads::ThreadIndex::build(&emails);
+        threads::print_threads(&thread_index.threads());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_address_strips_display_name_and_lowercases() {
+        assert_eq!(
+            normalize_address("Bob Smith <Bob@Example.com>"),
+            Some("bob@example.com".to_string())
+        );
+    }
+
+    #[test]
+    fn normalize_address_accepts_a_bare_address() {
+        assert_eq!(
+            normalize_address("bob@example.com"),
+            Some("bob@example.com".to_string())
+        );
+    }
+
+    #[test]
+    fn normalize_address_does_not_panic_on_a_stray_close_angle_before_open() {
+        assert_eq!(
+            normalize_address("Bob >VIP< <bob@example.com>"),
+            Some("bob@example.com".to_string())
+        );
+    }
+
+    #[test]
+    fn normalize_address_rejects_values_without_an_at_sign() {
+        assert_eq!(normalize_address("not-an-address"), None);
+    }
+
+    #[test]
+    fn parse_address_list_splits_and_normalizes_each_entry() {
+        let addresses = parse_address_list("Alice <alice@example.com>, bob@example.com");
+        assert_eq!(
+            addresses,
+            vec!["alice@example.com".to_string(), "bob@example.com".to_string()]
+        );
+    }
 }
@@ -0,0 +1,233 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::mail::unfold_headers;
+
+pub type MessageId = String;
+
+#[derive(Clone)]
+pub struct Message {
+    pub id: MessageId,
+    pub subject: String,
+    pub date: String,
+}
+
+/// One node of a reconstructed reply forest: a message plus the replies
+/// that named it as their parent.
+pub struct ThreadTree {
+    pub message: Message,
+    pub children: Vec<ThreadTree>,
+}
+
+/// Index of every message keyed by its own `Message-ID`, together with the
+/// parent/child links derived from `In-Reply-To`/`References`.
+pub struct ThreadIndex {
+    messages: HashMap<MessageId, Message>,
+    children: HashMap<MessageId, Vec<MessageId>>,
+    roots: Vec<MessageId>,
+}
+
+impl ThreadIndex {
+    /// Parse each email's `Message-ID`, `In-Reply-To`, and `References`
+    /// headers and build the reply forest. A message's parent is the last
+    /// id listed in `References`, falling back to `In-Reply-To`. Messages
+    /// whose parent is unknown or missing become roots.
+    pub fn build(emails: &[String]) -> Self {
+        let mut messages: HashMap<MessageId, Message> = HashMap::new();
+        let mut parents: HashMap<MessageId, MessageId> = HashMap::new();
+        let mut order: Vec<MessageId> = Vec::new();
+
+        for email in emails {
+            let headers = unfold_headers(email);
+
+            let id = match headers.get("message-id").and_then(|v| extract_message_id(v)) {
+                Some(id) => id,
+                None => continue,
+            };
+
+            let parent = headers
+                .get("references")
+                .and_then(|refs| last_message_id(refs))
+                .or_else(|| headers.get("in-reply-to").and_then(|v| extract_message_id(v)));
+
+            if let Some(parent) = parent {
+                // Don't let a message name itself as its own parent.
+                if parent != id {
+                    parents.insert(id.clone(), parent);
+                }
+            }
+
+            let subject = headers.get("subject").cloned().unwrap_or_default();
+            let date = headers.get("date").cloned().unwrap_or_default();
+
+            order.push(id.clone());
+            messages.insert(id.clone(), Message { id, subject, date });
+        }
+
+        // Break reference cycles before building the forest: walk each
+        // message's ancestor chain and, if it loops back on itself, drop
+        // the parent link at the point of the repeat. A true cycle is
+        // never reached by descending from an actual root, so without this
+        // the messages in it would silently vanish from `threads()`
+        // instead of surfacing anywhere.
+        let mut broken_parents: HashSet<MessageId> = HashSet::new();
+        for id in &order {
+            let mut seen: HashSet<MessageId> = HashSet::new();
+            let mut current = id.clone();
+
+            loop {
+                if !seen.insert(current.clone()) {
+                    broken_parents.insert(current);
+                    break;
+                }
+
+                match parents.get(&current) {
+                    Some(parent) if messages.contains_key(parent) => current = parent.clone(),
+                    _ => break,
+                }
+            }
+        }
+        for id in &broken_parents {
+            parents.remove(id);
+        }
+
+        let mut children: HashMap<MessageId, Vec<MessageId>> = HashMap::new();
+        let mut roots: Vec<MessageId> = Vec::new();
+
+        for id in &order {
+            match parents.get(id) {
+                // A dangling reference (parent never seen as a message) promotes the orphan to a root.
+                Some(parent) if messages.contains_key(parent) => {
+                    children.entry(parent.clone()).or_default().push(id.clone());
+                }
+                _ => roots.push(id.clone()),
+            }
+        }
+
+        Self {
+            messages,
+            children,
+            roots,
+        }
+    }
+
+    /// Build the reply forest, one [`ThreadTree`] per root message.
+    pub fn threads(&self) -> Vec<ThreadTree> {
+        self.roots
+            .iter()
+            .map(|root| self.build_tree(root, &mut HashSet::new()))
+            .collect()
+    }
+
+    fn build_tree(&self, id: &str, visited: &mut HashSet<MessageId>) -> ThreadTree {
+        let message = self.messages[id].clone();
+
+        // Guard against reference cycles: never descend into a message we're already rendering.
+        let children = if visited.insert(id.to_string()) {
+            self.children
+                .get(id)
+                .map(|ids| {
+                    ids.iter()
+                        .map(|child| self.build_tree(child, visited))
+                        .collect()
+                })
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+
+        ThreadTree { message, children }
+    }
+}
+
+/// Pretty-print each thread as an indented DFS: replies are indented one
+/// level deeper than their parent, showing subject and date.
+pub fn print_threads(trees: &[ThreadTree]) {
+    for tree in trees {
+        print_tree(tree, 0);
+    }
+}
+
+fn print_tree(tree: &ThreadTree, depth: usize) {
+    let indent = "  ".repeat(depth);
+    println!(
+        "{}- {} ({}) <{}>",
+        indent, tree.message.subject, tree.message.date, tree.message.id
+    );
+    for child in &tree.children {
+        print_tree(child, depth + 1);
+    }
+}
+
+/// Extract the bare id from a `Message-ID`/`In-Reply-To` value such as
+/// `<abc123@example.com>`, keeping the angle brackets stripped.
+fn extract_message_id(raw: &str) -> Option<MessageId> {
+    let raw = raw.trim();
+    let start = raw.find('<')?;
+    let end = raw[start..].find('>').map(|i| start + i)?;
+    let id = raw[start + 1..end].trim();
+    if id.is_empty() {
+        None
+    } else {
+        Some(id.to_string())
+    }
+}
+
+/// `References` is a whitespace-separated list of `<id>` tokens in
+/// chronological order; the parent is the last one listed.
+fn last_message_id(raw: &str) -> Option<MessageId> {
+    raw.split_whitespace()
+        .filter_map(extract_message_id)
+        .next_back()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn email(id: &str, in_reply_to: Option<&str>) -> String {
+        match in_reply_to {
+            Some(parent) => format!(
+                "Message-ID: <{}>\nIn-Reply-To: <{}>\nSubject: s\nDate: d\n\nbody",
+                id, parent
+            ),
+            None => format!("Message-ID: <{}>\nSubject: s\nDate: d\n\nbody", id),
+        }
+    }
+
+    fn count_nodes(tree: &ThreadTree) -> usize {
+        1 + tree.children.iter().map(count_nodes).sum::<usize>()
+    }
+
+    #[test]
+    fn builds_a_simple_reply_chain() {
+        let emails = vec![email("a", None), email("b", Some("a"))];
+        let index = ThreadIndex::build(&emails);
+        let trees = index.threads();
+
+        assert_eq!(trees.len(), 1);
+        assert_eq!(trees[0].message.id, "a");
+        assert_eq!(trees[0].children.len(), 1);
+        assert_eq!(trees[0].children[0].message.id, "b");
+    }
+
+    #[test]
+    fn a_dangling_reference_is_promoted_to_a_root() {
+        let emails = vec![email("b", Some("missing-parent"))];
+        let index = ThreadIndex::build(&emails);
+        let trees = index.threads();
+
+        assert_eq!(trees.len(), 1);
+        assert_eq!(trees[0].message.id, "b");
+    }
+
+    #[test]
+    fn a_reference_cycle_does_not_drop_its_messages() {
+        // a's parent is b, b's parent is a: a genuine two-message cycle.
+        let emails = vec![email("a", Some("b")), email("b", Some("a"))];
+        let index = ThreadIndex::build(&emails);
+        let trees = index.threads();
+
+        let total: usize = trees.iter().map(count_nodes).sum();
+        assert_eq!(total, 2, "both messages in the cycle must surface somewhere");
+    }
+}